@@ -0,0 +1,125 @@
+//! Minimal BlurHash encoder used to produce compact placeholder strings for
+//! `recent_files.json` so the frontend can render a blurred preview before
+//! the full `open_image` data URL is ready.
+
+use image::RgbaImage;
+
+const BASE83_ALPHABET: &[u8] =
+    b"0123456789ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz#$%*+,-.:;=?@[]^_{|}~";
+
+fn srgb_to_linear_table() -> [f64; 256] {
+    let mut table = [0.0; 256];
+    for (c, entry) in table.iter_mut().enumerate() {
+        let v = c as f64 / 255.0;
+        *entry = if v <= 10.31 / 255.0 {
+            v / 12.92
+        } else {
+            ((v + 0.055) / 1.055).powf(2.4)
+        };
+    }
+    table
+}
+
+fn encode_base83(value: u32, length: usize, out: &mut String) {
+    for i in (0..length).rev() {
+        let digit = (value / 83u32.pow(i as u32)) % 83;
+        out.push(BASE83_ALPHABET[digit as usize] as char);
+    }
+}
+
+fn linear_to_srgb8(v: f64) -> u8 {
+    let v = v.clamp(0.0, 1.0);
+    let srgb = if v <= 0.0031308 {
+        v * 12.92
+    } else {
+        1.055 * v.powf(1.0 / 2.4) - 0.055
+    };
+    (srgb * 255.0).round().clamp(0.0, 255.0) as u8
+}
+
+fn sign(v: f64) -> f64 {
+    if v < 0.0 {
+        -1.0
+    } else {
+        1.0
+    }
+}
+
+fn quantize(value: f64, max_value: f64) -> i32 {
+    let v = sign(value) * (value.abs() / max_value).powf(0.5) * 9.0 + 9.5;
+    v.floor().clamp(0.0, 18.0) as i32
+}
+
+/// Encodes `img` into a BlurHash string using `components_x` by `components_y`
+/// basis functions (each must be in `1..=9`).
+pub fn encode(img: &RgbaImage, components_x: u32, components_y: u32) -> Result<String, String> {
+    if !(1..=9).contains(&components_x) || !(1..=9).contains(&components_y) {
+        return Err("components_x and components_y must be between 1 and 9".to_string());
+    }
+
+    let (w, h) = (img.width(), img.height());
+    if w == 0 || h == 0 {
+        return Err("image has zero dimensions".to_string());
+    }
+
+    let to_linear = srgb_to_linear_table();
+    let mut factors = Vec::with_capacity((components_x * components_y) as usize);
+
+    for j in 0..components_y {
+        for i in 0..components_x {
+            let normalisation = if i == 0 && j == 0 { 1.0 } else { 2.0 };
+            let mut sum = [0.0f64; 3];
+
+            for py in 0..h {
+                let basis_y = (std::f64::consts::PI * j as f64 * py as f64 / h as f64).cos();
+                for px in 0..w {
+                    let basis_x = (std::f64::consts::PI * i as f64 * px as f64 / w as f64).cos();
+                    let basis = basis_x * basis_y;
+                    let pixel = img.get_pixel(px, py);
+                    sum[0] += basis * to_linear[pixel[0] as usize];
+                    sum[1] += basis * to_linear[pixel[1] as usize];
+                    sum[2] += basis * to_linear[pixel[2] as usize];
+                }
+            }
+
+            let scale = normalisation / (w as f64 * h as f64);
+            factors.push([sum[0] * scale, sum[1] * scale, sum[2] * scale]);
+        }
+    }
+
+    let dc = factors[0];
+    let ac = &factors[1..];
+
+    let mut result = String::new();
+
+    let size_flag = (components_x - 1) + (components_y - 1) * 9;
+    encode_base83(size_flag, 1, &mut result);
+
+    let actual_max = ac
+        .iter()
+        .flat_map(|c| c.iter().map(|v| v.abs()))
+        .fold(0.0_f64, f64::max);
+
+    let quantized_max_value = ((actual_max * 166.0 - 0.5).floor() as i32).clamp(0, 82) as u32;
+    encode_base83(quantized_max_value, 1, &mut result);
+
+    // A decoder reconstructs the max from the quantized header byte, not from
+    // the true float value, so AC components must be quantized against that
+    // same dequantized max or the decoded colors drift from the source image.
+    let max_value = (quantized_max_value as f64 + 1.0) / 166.0;
+
+    let dc_value = (linear_to_srgb8(dc[0]) as u32) << 16
+        | (linear_to_srgb8(dc[1]) as u32) << 8
+        | linear_to_srgb8(dc[2]) as u32;
+    encode_base83(dc_value, 4, &mut result);
+
+    for component in ac {
+        let qr = quantize(component[0], max_value);
+        let qg = quantize(component[1], max_value);
+        let qb = quantize(component[2], max_value);
+        let value = (qr * 19 * 19 + qg * 19 + qb) as u32;
+        encode_base83(value, 2, &mut result);
+    }
+
+    Ok(result)
+}
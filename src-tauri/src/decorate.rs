@@ -0,0 +1,206 @@
+//! Post-crop/scale "decorate" stage: wraps the edited image with padding, a
+//! solid or gradient background, rounded corners, and a soft drop shadow —
+//! the mockup/screenshot framing step that runs right before `export_image`
+//! saves the final file.
+
+use crate::blur;
+use image::{Rgba, RgbaImage};
+
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct BackgroundFill {
+    /// "solid" or "gradient".
+    pub kind: String,
+    pub color: (u8, u8, u8, u8),
+    pub gradient_color_2: (u8, u8, u8, u8),
+    pub gradient_angle: f64,
+}
+
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct ShadowSettings {
+    pub offset_x: f64,
+    pub offset_y: f64,
+    pub blur: f64,
+    pub color: (u8, u8, u8, u8),
+}
+
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct Decoration {
+    pub padding: u32,
+    pub background: BackgroundFill,
+    pub corner_radius: u32,
+    pub shadow: Option<ShadowSettings>,
+}
+
+fn round_corners(img: &RgbaImage, radius: u32) -> RgbaImage {
+    if radius == 0 {
+        return img.clone();
+    }
+    let (w, h) = (img.width(), img.height());
+    let r = radius.min(w / 2).min(h / 2) as f64;
+    let mut out = img.clone();
+    for y in 0..h {
+        for x in 0..w {
+            let dx = if (x as f64) < r {
+                r - x as f64 - 0.5
+            } else if (x as f64) > w as f64 - r {
+                x as f64 + 0.5 - (w as f64 - r)
+            } else {
+                0.0
+            };
+            let dy = if (y as f64) < r {
+                r - y as f64 - 0.5
+            } else if (y as f64) > h as f64 - r {
+                y as f64 + 0.5 - (h as f64 - r)
+            } else {
+                0.0
+            };
+            if dx > 0.0 && dy > 0.0 && (dx * dx + dy * dy).sqrt() > r {
+                out.get_pixel_mut(x, y)[3] = 0;
+            }
+        }
+    }
+    out
+}
+
+fn paint_background(canvas: &mut RgbaImage, fill: &BackgroundFill) {
+    let (w, h) = (canvas.width(), canvas.height());
+    if fill.kind != "gradient" {
+        let color = Rgba([fill.color.0, fill.color.1, fill.color.2, fill.color.3]);
+        for pixel in canvas.pixels_mut() {
+            *pixel = color;
+        }
+        return;
+    }
+
+    let angle = fill.gradient_angle.to_radians();
+    let (dx, dy) = (angle.cos(), angle.sin());
+    let corners = [(0.0, 0.0), (w as f64, 0.0), (0.0, h as f64), (w as f64, h as f64)];
+    let projections: Vec<f64> = corners.iter().map(|(x, y)| x * dx + y * dy).collect();
+    let min_proj = projections.iter().cloned().fold(f64::INFINITY, f64::min);
+    let max_proj = projections.iter().cloned().fold(f64::NEG_INFINITY, f64::max);
+    let span = (max_proj - min_proj).max(1e-6);
+
+    let c1 = fill.color;
+    let c2 = fill.gradient_color_2;
+    for y in 0..h {
+        for x in 0..w {
+            let proj = x as f64 * dx + y as f64 * dy;
+            let t = ((proj - min_proj) / span).clamp(0.0, 1.0);
+            let lerp = |a: u8, b: u8| (a as f64 + (b as f64 - a as f64) * t).round() as u8;
+            canvas.put_pixel(
+                x,
+                y,
+                Rgba([
+                    lerp(c1.0, c2.0),
+                    lerp(c1.1, c2.1),
+                    lerp(c1.2, c2.2),
+                    lerp(c1.3, c2.3),
+                ]),
+            );
+        }
+    }
+}
+
+fn alpha_composite(base: &mut RgbaImage, overlay: &RgbaImage, ox: i32, oy: i32) {
+    let (bw, bh) = (base.width() as i32, base.height() as i32);
+    for y in 0..overlay.height() as i32 {
+        let dy = oy + y;
+        if dy < 0 || dy >= bh {
+            continue;
+        }
+        for x in 0..overlay.width() as i32 {
+            let dx = ox + x;
+            if dx < 0 || dx >= bw {
+                continue;
+            }
+            let src = *overlay.get_pixel(x as u32, y as u32);
+            let src_a = src[3] as f64 / 255.0;
+            if src_a <= 0.0 {
+                continue;
+            }
+            let dst = base.get_pixel_mut(dx as u32, dy as u32);
+            let dst_a = dst[3] as f64 / 255.0;
+            let out_a = src_a + dst_a * (1.0 - src_a);
+            if out_a <= 0.0 {
+                continue;
+            }
+            for c in 0..3 {
+                let blended =
+                    (src[c] as f64 * src_a + dst[c] as f64 * dst_a * (1.0 - src_a)) / out_a;
+                dst[c] = blended.round().clamp(0.0, 255.0) as u8;
+            }
+            dst[3] = (out_a * 255.0).round().clamp(0.0, 255.0) as u8;
+        }
+    }
+}
+
+/// Renders a blurred drop shadow from `img`'s alpha silhouette, sized to fit
+/// `img` plus the blur radius on every side. The returned offsets place the
+/// shadow relative to `img`'s top-left corner.
+fn render_shadow(img: &RgbaImage, shadow: &ShadowSettings) -> (RgbaImage, i32, i32) {
+    let (w, h) = (img.width() as usize, img.height() as usize);
+    let margin = shadow.blur.max(0.0).ceil() as usize;
+    let (sw, sh) = (w + margin * 2, h + margin * 2);
+    let mut alpha = vec![0.0f32; sw * sh];
+    for (x, y, p) in img.enumerate_pixels() {
+        alpha[(y as usize + margin) * sw + x as usize + margin] = p[3] as f32 / 255.0;
+    }
+    let blurred = blur::gaussian_blur_channel(&alpha, sw, sh, shadow.blur.max(0.0));
+
+    let mut out = RgbaImage::new(sw as u32, sh as u32);
+    let color = shadow.color;
+    for (idx, a) in blurred.iter().enumerate() {
+        let x = (idx % sw) as u32;
+        let y = (idx / sw) as u32;
+        let a = (*a * color.3 as f32 / 255.0 * 255.0).round().clamp(0.0, 255.0) as u8;
+        out.put_pixel(x, y, Rgba([color.0, color.1, color.2, a]));
+    }
+
+    (
+        out,
+        shadow.offset_x.round() as i32 - margin as i32,
+        shadow.offset_y.round() as i32 - margin as i32,
+    )
+}
+
+/// Composites `img` onto a larger canvas with padding, background, rounded
+/// corners, and a drop shadow, per `settings`.
+pub fn apply(img: &RgbaImage, settings: &Decoration) -> RgbaImage {
+    let rounded = round_corners(img, settings.corner_radius);
+    let padding = settings.padding;
+
+    let shadow_margin = settings
+        .shadow
+        .as_ref()
+        .map(|s| (s.blur.abs() + s.offset_x.abs().max(s.offset_y.abs())).ceil() as u32)
+        .unwrap_or(0);
+
+    let canvas_w = rounded.width() + padding * 2 + shadow_margin * 2;
+    let canvas_h = rounded.height() + padding * 2 + shadow_margin * 2;
+    let mut canvas = RgbaImage::new(canvas_w, canvas_h);
+    paint_background(&mut canvas, &settings.background);
+
+    let image_x = (padding + shadow_margin) as i32;
+    let image_y = (padding + shadow_margin) as i32;
+
+    if let Some(shadow) = &settings.shadow {
+        let (shadow_img, sx, sy) = render_shadow(&rounded, shadow);
+        alpha_composite(&mut canvas, &shadow_img, image_x + sx, image_y + sy);
+    }
+
+    alpha_composite(&mut canvas, &rounded, image_x, image_y);
+    canvas
+}
+
+/// Flattens `img`'s transparency against a solid `background` color, for
+/// formats (JPEG) that can't carry an alpha channel. Unlike a bare
+/// `to_rgb8()`, this actually blends rather than discarding alpha, so
+/// decorated exports don't turn padding/shadow regions black.
+pub fn flatten_over_background(img: &RgbaImage, background: Rgba<u8>) -> RgbaImage {
+    let mut canvas = RgbaImage::new(img.width(), img.height());
+    for pixel in canvas.pixels_mut() {
+        *pixel = background;
+    }
+    alpha_composite(&mut canvas, img, 0, 0);
+    canvas
+}
@@ -0,0 +1,279 @@
+//! Ordered filter chain applied to the RGBA buffer in `export_image` and
+//! `apply_edits`. Each op is applied in the order the frontend supplies it,
+//! so a reorderable effects list in the UI maps directly onto this `Vec`
+//! instead of a fixed pipeline stage order.
+
+use crate::blur;
+use image::{Rgba, RgbaImage};
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type")]
+pub enum FilterOp {
+    Blur { radius: f64 },
+    Sharpen { amount: f64 },
+    Saturate { factor: f64 },
+    HueRotate { degrees: f64 },
+    Sepia { amount: f64 },
+    Brightness { amount: f64 },
+    Contrast { amount: f64 },
+    Invert,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub enum MixBlendMode {
+    Multiply,
+    Screen,
+    Overlay,
+    Darken,
+    Lighten,
+    ColorDodge,
+    Difference,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Overlay {
+    pub image_path: String,
+    pub blend_mode: MixBlendMode,
+    pub opacity: f64,
+}
+
+pub fn apply_chain(img: &mut RgbaImage, ops: &[FilterOp]) {
+    for op in ops {
+        match op {
+            FilterOp::Blur { radius } => blur_rgba(img, *radius),
+            FilterOp::Sharpen { amount } => sharpen(img, *amount),
+            FilterOp::Saturate { factor } => saturate(img, *factor),
+            FilterOp::HueRotate { degrees } => hue_rotate(img, *degrees),
+            FilterOp::Sepia { amount } => sepia(img, *amount),
+            FilterOp::Brightness { amount } => brightness(img, *amount),
+            FilterOp::Contrast { amount } => contrast(img, *amount),
+            FilterOp::Invert => invert(img),
+        }
+    }
+}
+
+fn channel_buffers(img: &RgbaImage) -> [Vec<f32>; 3] {
+    let (w, h) = (img.width() as usize, img.height() as usize);
+    let mut channels = [
+        Vec::with_capacity(w * h),
+        Vec::with_capacity(w * h),
+        Vec::with_capacity(w * h),
+    ];
+    for pixel in img.pixels() {
+        for c in 0..3 {
+            channels[c].push(pixel[c] as f32);
+        }
+    }
+    channels
+}
+
+fn blur_rgba(img: &mut RgbaImage, radius: f64) {
+    if radius <= 0.0 {
+        return;
+    }
+    let (w, h) = (img.width() as usize, img.height() as usize);
+    let channels = channel_buffers(img);
+    let blurred: Vec<Vec<f32>> = channels
+        .iter()
+        .map(|c| blur::gaussian_blur_channel(c, w, h, radius))
+        .collect();
+    for (idx, pixel) in img.pixels_mut().enumerate() {
+        for c in 0..3 {
+            pixel[c] = blurred[c][idx].round().clamp(0.0, 255.0) as u8;
+        }
+    }
+}
+
+/// Unsharp mask: `original + amount * (original - blurred)`.
+fn sharpen(img: &mut RgbaImage, amount: f64) {
+    if amount <= 0.0 {
+        return;
+    }
+    let (w, h) = (img.width() as usize, img.height() as usize);
+    let channels = channel_buffers(img);
+    let blurred: Vec<Vec<f32>> = channels
+        .iter()
+        .map(|c| blur::gaussian_blur_channel(c, w, h, 1.5))
+        .collect();
+    for (idx, pixel) in img.pixels_mut().enumerate() {
+        for c in 0..3 {
+            let original = channels[c][idx] as f64;
+            let blur_value = blurred[c][idx] as f64;
+            let value = original + amount * (original - blur_value);
+            pixel[c] = value.round().clamp(0.0, 255.0) as u8;
+        }
+    }
+}
+
+fn rgb_to_hsl(r: f64, g: f64, b: f64) -> (f64, f64, f64) {
+    let max = r.max(g).max(b);
+    let min = r.min(g).min(b);
+    let l = (max + min) / 2.0;
+    if (max - min).abs() < f64::EPSILON {
+        return (0.0, 0.0, l);
+    }
+    let d = max - min;
+    let s = if l > 0.5 { d / (2.0 - max - min) } else { d / (max + min) };
+    let h = if max == r {
+        (g - b) / d + if g < b { 6.0 } else { 0.0 }
+    } else if max == g {
+        (b - r) / d + 2.0
+    } else {
+        (r - g) / d + 4.0
+    } / 6.0;
+    (h, s, l)
+}
+
+fn hue_to_rgb(p: f64, q: f64, mut t: f64) -> f64 {
+    if t < 0.0 {
+        t += 1.0;
+    }
+    if t > 1.0 {
+        t -= 1.0;
+    }
+    if t < 1.0 / 6.0 {
+        return p + (q - p) * 6.0 * t;
+    }
+    if t < 1.0 / 2.0 {
+        return q;
+    }
+    if t < 2.0 / 3.0 {
+        return p + (q - p) * (2.0 / 3.0 - t) * 6.0;
+    }
+    p
+}
+
+fn hsl_to_rgb(h: f64, s: f64, l: f64) -> (f64, f64, f64) {
+    if s.abs() < f64::EPSILON {
+        return (l, l, l);
+    }
+    let q = if l < 0.5 { l * (1.0 + s) } else { l + s - l * s };
+    let p = 2.0 * l - q;
+    (
+        hue_to_rgb(p, q, h + 1.0 / 3.0),
+        hue_to_rgb(p, q, h),
+        hue_to_rgb(p, q, h - 1.0 / 3.0),
+    )
+}
+
+fn saturate(img: &mut RgbaImage, factor: f64) {
+    for pixel in img.pixels_mut() {
+        let (h, s, l) = rgb_to_hsl(
+            pixel[0] as f64 / 255.0,
+            pixel[1] as f64 / 255.0,
+            pixel[2] as f64 / 255.0,
+        );
+        let (r, g, b) = hsl_to_rgb(h, (s * factor).clamp(0.0, 1.0), l);
+        pixel[0] = (r * 255.0).round().clamp(0.0, 255.0) as u8;
+        pixel[1] = (g * 255.0).round().clamp(0.0, 255.0) as u8;
+        pixel[2] = (b * 255.0).round().clamp(0.0, 255.0) as u8;
+    }
+}
+
+fn hue_rotate(img: &mut RgbaImage, degrees: f64) {
+    let shift = degrees / 360.0;
+    for pixel in img.pixels_mut() {
+        let (h, s, l) = rgb_to_hsl(
+            pixel[0] as f64 / 255.0,
+            pixel[1] as f64 / 255.0,
+            pixel[2] as f64 / 255.0,
+        );
+        let h = (h + shift).rem_euclid(1.0);
+        let (r, g, b) = hsl_to_rgb(h, s, l);
+        pixel[0] = (r * 255.0).round().clamp(0.0, 255.0) as u8;
+        pixel[1] = (g * 255.0).round().clamp(0.0, 255.0) as u8;
+        pixel[2] = (b * 255.0).round().clamp(0.0, 255.0) as u8;
+    }
+}
+
+fn sepia(img: &mut RgbaImage, amount: f64) {
+    let amount = amount.clamp(0.0, 1.0);
+    for pixel in img.pixels_mut() {
+        let (r, g, b) = (pixel[0] as f64, pixel[1] as f64, pixel[2] as f64);
+        let sr = 0.393 * r + 0.769 * g + 0.189 * b;
+        let sg = 0.349 * r + 0.686 * g + 0.168 * b;
+        let sb = 0.272 * r + 0.534 * g + 0.131 * b;
+        pixel[0] = (r + (sr - r) * amount).round().clamp(0.0, 255.0) as u8;
+        pixel[1] = (g + (sg - g) * amount).round().clamp(0.0, 255.0) as u8;
+        pixel[2] = (b + (sb - b) * amount).round().clamp(0.0, 255.0) as u8;
+    }
+}
+
+fn brightness(img: &mut RgbaImage, amount: f64) {
+    let b = (amount * 255.0) as i32;
+    for pixel in img.pixels_mut() {
+        for c in 0..3 {
+            pixel[c] = (pixel[c] as i32 + b).clamp(0, 255) as u8;
+        }
+    }
+}
+
+fn contrast(img: &mut RgbaImage, amount: f64) {
+    let c = amount + 1.0;
+    for pixel in img.pixels_mut() {
+        for i in 0..3 {
+            let v = pixel[i] as f64;
+            pixel[i] = ((v - 128.0) * c + 128.0).clamp(0.0, 255.0) as u8;
+        }
+    }
+}
+
+fn invert(img: &mut RgbaImage) {
+    for pixel in img.pixels_mut() {
+        for c in 0..3 {
+            pixel[c] = 255 - pixel[c];
+        }
+    }
+}
+
+fn blend_channel(mode: &MixBlendMode, base: f64, top: f64) -> f64 {
+    match mode {
+        MixBlendMode::Multiply => base * top,
+        MixBlendMode::Screen => 1.0 - (1.0 - base) * (1.0 - top),
+        MixBlendMode::Overlay => {
+            if base <= 0.5 {
+                2.0 * base * top
+            } else {
+                1.0 - 2.0 * (1.0 - base) * (1.0 - top)
+            }
+        }
+        MixBlendMode::Darken => base.min(top),
+        MixBlendMode::Lighten => base.max(top),
+        MixBlendMode::ColorDodge => {
+            if top >= 1.0 {
+                1.0
+            } else {
+                (base / (1.0 - top)).min(1.0)
+            }
+        }
+        MixBlendMode::Difference => (base - top).abs(),
+    }
+}
+
+/// Composites `overlay_img` onto `img` using `blend_mode`, scaled by
+/// `opacity`, after the filter chain has run. `overlay_img` is expected to
+/// already match `img`'s dimensions.
+pub fn apply_overlay(img: &mut RgbaImage, overlay_img: &RgbaImage, blend_mode: &MixBlendMode, opacity: f64) {
+    let opacity = opacity.clamp(0.0, 1.0);
+    let (w, h) = (img.width(), img.height());
+    for y in 0..h.min(overlay_img.height()) {
+        for x in 0..w.min(overlay_img.width()) {
+            let base = *img.get_pixel(x, y);
+            let top = *overlay_img.get_pixel(x, y);
+            let top_a = (top[3] as f64 / 255.0) * opacity;
+            if top_a <= 0.0 {
+                continue;
+            }
+            let mut blended = [0u8; 3];
+            for c in 0..3 {
+                let b = base[c] as f64 / 255.0;
+                let t = top[c] as f64 / 255.0;
+                let mixed = blend_channel(blend_mode, b, t);
+                let value = b * (1.0 - top_a) + mixed * top_a;
+                blended[c] = (value * 255.0).round().clamp(0.0, 255.0) as u8;
+            }
+            img.put_pixel(x, y, Rgba([blended[0], blended[1], blended[2], base[3]]));
+        }
+    }
+}
@@ -1,3 +1,11 @@
+mod blur;
+mod blurhash;
+mod decorate;
+mod filters;
+mod png_optimize;
+mod raw_decode;
+mod resizing;
+
 use base64::Engine;
 use image::{DynamicImage, GenericImageView, ImageEncoder, Rgba, RgbaImage};
 use serde::{Deserialize, Serialize};
@@ -10,6 +18,18 @@ pub struct ImageInfo {
     pub width: u32,
     pub height: u32,
     pub data_url: String,
+    pub raw_source: raw_decode::RawSourceInfo,
+}
+
+/// Opens `path`, routing camera RAW files through `raw_decode` and
+/// everything else through `image::open`.
+fn open_any_image(path: &str) -> Result<(DynamicImage, raw_decode::RawSourceInfo), String> {
+    if raw_decode::is_raw_path(path) {
+        raw_decode::decode(path)
+    } else {
+        let img = image::open(path).map_err(|e| format!("Failed to open image: {}", e))?;
+        Ok((img, raw_decode::RawSourceInfo::default()))
+    }
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -46,17 +66,32 @@ pub struct ExportPayload {
     pub flip_h: bool,
     pub flip_v: bool,
     pub grayscale: bool,
-    pub brightness: f64,
-    pub contrast: f64,
     pub pixelate_strokes: Vec<PixelateStroke>,
     pub pixelate_block_size: u32,
     pub bg_removal: Option<BgRemovalSettings>,
     pub mode: String,
+    pub png_optimization_level: u8,
+    pub strip_metadata: bool,
+    pub resize_filter: String,
+    pub decoration: Option<decorate::Decoration>,
+    pub filters: Vec<filters::FilterOp>,
+    pub overlay: Option<filters::Overlay>,
 }
 
 #[tauri::command]
 fn open_image(path: String) -> Result<ImageInfo, String> {
-    let img = image::open(&path).map_err(|e| format!("Failed to open image: {}", e))?;
+    // For RAW files, prefer the embedded JPEG thumbnail so the preview shows
+    // up immediately; the full sensor decode is much slower and the frontend
+    // re-requests it (via export/apply) once the user actually edits.
+    let (img, raw_source) = if raw_decode::is_raw_path(&path) {
+        match raw_decode::embedded_thumbnail(&path) {
+            Some((thumb, info)) => (thumb, info),
+            None => open_any_image(&path)?,
+        }
+    } else {
+        open_any_image(&path)?
+    };
+
     let (width, height) = img.dimensions();
 
     let rgba = img.to_rgba8();
@@ -71,13 +106,19 @@ fn open_image(path: String) -> Result<ImageInfo, String> {
         width,
         height,
         data_url,
+        raw_source,
     })
 }
 
+#[tauri::command]
+fn generate_blurhash(path: String, components_x: u32, components_y: u32) -> Result<String, String> {
+    let (img, _raw_source) = open_any_image(&path)?;
+    blurhash::encode(&img.to_rgba8(), components_x, components_y)
+}
+
 #[tauri::command]
 fn export_image(payload: ExportPayload) -> Result<String, String> {
-    let mut img =
-        image::open(&payload.source_path).map_err(|e| format!("Failed to open image: {}", e))?;
+    let (mut img, _raw_source) = open_any_image(&payload.source_path)?;
 
     // 1. Rotate
     img = match payload.rotation {
@@ -100,12 +141,17 @@ fn export_image(payload: ExportPayload) -> Result<String, String> {
         img = DynamicImage::ImageLuma8(img.to_luma8()).to_rgba8().into();
     }
 
-    // 4. Brightness & Contrast
-    if payload.brightness != 0.0 || payload.contrast != 0.0 {
+    // 4. Filter chain (blur, sharpen, saturate, hue-rotate, sepia,
+    // brightness, contrast, invert) applied in the order the frontend gives,
+    // followed by an optional blend-mode overlay.
+    if !payload.filters.is_empty() {
         let mut rgba = img.to_rgba8();
-        apply_brightness_contrast(&mut rgba, payload.brightness, payload.contrast);
+        filters::apply_chain(&mut rgba, &payload.filters);
         img = DynamicImage::ImageRgba8(rgba);
     }
+    if let Some(ref overlay) = payload.overlay {
+        img = apply_overlay_to(img, overlay)?;
+    }
 
     // 5. Pixelate strokes
     if !payload.pixelate_strokes.is_empty() {
@@ -146,23 +192,45 @@ fn export_image(payload: ExportPayload) -> Result<String, String> {
         img = img.crop_imm(cx, cy, cw, ch);
     }
 
+    let resize_filter = resizing::ResizeFilter::parse(&payload.resize_filter);
     if payload.target_width > 0 && payload.target_height > 0 {
         if payload.mode == "scale_then_crop" {
-            img = scale_then_crop(img, payload.target_width, payload.target_height);
+            img = scale_then_crop(img, payload.target_width, payload.target_height, resize_filter);
         } else {
-            img = img.resize_exact(
+            img = DynamicImage::ImageRgba8(resizing::resize(
+                &img.to_rgba8(),
                 payload.target_width,
                 payload.target_height,
-                image::imageops::FilterType::Lanczos3,
-            );
+                resize_filter,
+            ));
         }
     }
 
-    // 8. Save
+    // 8. Decorate
+    if let Some(ref decoration) = payload.decoration {
+        img = DynamicImage::ImageRgba8(decorate::apply(&img.to_rgba8(), decoration));
+    }
+
+    // 9. Save
     let output_path = PathBuf::from(&payload.output_path);
     match payload.output_format.as_str() {
         "jpeg" | "jpg" => {
-            let rgb = img.to_rgb8();
+            let rgb = match &payload.decoration {
+                Some(decoration) => {
+                    let background = Rgba([
+                        decoration.background.color.0,
+                        decoration.background.color.1,
+                        decoration.background.color.2,
+                        255,
+                    ]);
+                    DynamicImage::ImageRgba8(decorate::flatten_over_background(
+                        &img.to_rgba8(),
+                        background,
+                    ))
+                    .to_rgb8()
+                }
+                None => img.to_rgb8(),
+            };
             let mut writer =
                 std::io::BufWriter::new(fs::File::create(&output_path).map_err(|e| e.to_string())?);
             let encoder = image::codecs::jpeg::JpegEncoder::new_with_quality(
@@ -179,35 +247,47 @@ fn export_image(payload: ExportPayload) -> Result<String, String> {
                 .map_err(|e: image::ImageError| e.to_string())?;
         }
         _ => {
-            img.save_with_format(&output_path, image::ImageFormat::Png)
+            let mut png_buf = std::io::Cursor::new(Vec::new());
+            img.write_to(&mut png_buf, image::ImageFormat::Png)
                 .map_err(|e| e.to_string())?;
+            let png_bytes = png_optimize::optimize(
+                png_buf.into_inner(),
+                payload.png_optimization_level,
+                payload.strip_metadata,
+            )?;
+            fs::write(&output_path, png_bytes).map_err(|e| e.to_string())?;
         }
     }
 
     Ok(payload.output_path)
 }
 
-fn scale_then_crop(img: DynamicImage, tw: u32, th: u32) -> DynamicImage {
+/// Applies `overlay` to `img` (converted to RGBA): loads the overlay source
+/// through the same RAW-aware path as the main image, resizes it to match,
+/// and blends it in per `overlay.blend_mode`/`overlay.opacity`.
+fn apply_overlay_to(img: DynamicImage, overlay: &filters::Overlay) -> Result<DynamicImage, String> {
+    let (w, h) = img.dimensions();
+    let (overlay_src, _) = open_any_image(&overlay.image_path)?;
+    let overlay_img = resizing::resize(&overlay_src.to_rgba8(), w, h, resizing::ResizeFilter::Triangle);
+    let mut rgba = img.to_rgba8();
+    filters::apply_overlay(&mut rgba, &overlay_img, &overlay.blend_mode, overlay.opacity);
+    Ok(DynamicImage::ImageRgba8(rgba))
+}
+
+fn scale_then_crop(
+    img: DynamicImage,
+    tw: u32,
+    th: u32,
+    filter: resizing::ResizeFilter,
+) -> DynamicImage {
     let (iw, ih) = img.dimensions();
     let scale = (tw as f64 / iw as f64).max(th as f64 / ih as f64);
     let sw = (iw as f64 * scale).round() as u32;
     let sh = (ih as f64 * scale).round() as u32;
-    let scaled = img.resize_exact(sw, sh, image::imageops::FilterType::Lanczos3);
+    let scaled = resizing::resize(&img.to_rgba8(), sw, sh, filter);
     let ox = (sw.saturating_sub(tw)) / 2;
     let oy = (sh.saturating_sub(th)) / 2;
-    scaled.crop_imm(ox, oy, tw.min(sw), th.min(sh))
-}
-
-fn apply_brightness_contrast(img: &mut RgbaImage, brightness: f64, contrast: f64) {
-    let b = (brightness * 255.0) as i32;
-    let c = contrast + 1.0;
-    for pixel in img.pixels_mut() {
-        for i in 0..3 {
-            let v = pixel[i] as f64;
-            let v = ((v - 128.0) * c + 128.0 + b as f64).clamp(0.0, 255.0);
-            pixel[i] = v as u8;
-        }
-    }
+    DynamicImage::ImageRgba8(scaled).crop_imm(ox, oy, tw.min(sw), th.min(sh))
 }
 
 fn pixelate_region(img: &mut RgbaImage, cx: i32, cy: i32, radius: i32, block_size: u32) {
@@ -282,18 +362,20 @@ pub struct ApplyPayload {
     pub flip_h: bool,
     pub flip_v: bool,
     pub grayscale: bool,
-    pub brightness: f64,
-    pub contrast: f64,
     pub pixelate_strokes: Vec<PixelateStroke>,
     pub pixelate_block_size: u32,
     pub resize_width: u32,
     pub resize_height: u32,
+    pub png_optimization_level: u8,
+    pub strip_metadata: bool,
+    pub resize_filter: String,
+    pub filters: Vec<filters::FilterOp>,
+    pub overlay: Option<filters::Overlay>,
 }
 
 #[tauri::command]
 fn apply_edits(app: tauri::AppHandle, payload: ApplyPayload) -> Result<ImageInfo, String> {
-    let mut img =
-        image::open(&payload.source_path).map_err(|e| format!("Failed to open image: {}", e))?;
+    let (mut img, raw_source) = open_any_image(&payload.source_path)?;
 
     // Rotate
     img = match payload.rotation {
@@ -316,12 +398,15 @@ fn apply_edits(app: tauri::AppHandle, payload: ApplyPayload) -> Result<ImageInfo
         img = DynamicImage::ImageLuma8(img.to_luma8()).to_rgba8().into();
     }
 
-    // Brightness & Contrast
-    if payload.brightness != 0.0 || payload.contrast != 0.0 {
+    // Filter chain + optional overlay
+    if !payload.filters.is_empty() {
         let mut rgba = img.to_rgba8();
-        apply_brightness_contrast(&mut rgba, payload.brightness, payload.contrast);
+        filters::apply_chain(&mut rgba, &payload.filters);
         img = DynamicImage::ImageRgba8(rgba);
     }
+    if let Some(ref overlay) = payload.overlay {
+        img = apply_overlay_to(img, overlay)?;
+    }
 
     // Pixelate strokes
     if !payload.pixelate_strokes.is_empty() {
@@ -355,21 +440,27 @@ fn apply_edits(app: tauri::AppHandle, payload: ApplyPayload) -> Result<ImageInfo
 
     // Resize
     if payload.resize_width > 0 && payload.resize_height > 0 {
-        let (cw, ch) = img.dimensions();
-        if payload.resize_width != cw || payload.resize_height != ch {
-            img = img.resize_exact(
-                payload.resize_width,
-                payload.resize_height,
-                image::imageops::FilterType::Lanczos3,
-            );
-        }
+        img = DynamicImage::ImageRgba8(resizing::resize(
+            &img.to_rgba8(),
+            payload.resize_width,
+            payload.resize_height,
+            resizing::ResizeFilter::parse(&payload.resize_filter),
+        ));
     }
 
     // Save to temp file
     let data_dir = app.path().app_data_dir().map_err(|e| e.to_string())?;
     fs::create_dir_all(&data_dir).map_err(|e| e.to_string())?;
     let temp_path = data_dir.join("_applied.png");
-    img.save_with_format(&temp_path, image::ImageFormat::Png)
+    let mut temp_png_buf = std::io::Cursor::new(Vec::new());
+    img.write_to(&mut temp_png_buf, image::ImageFormat::Png)
+        .map_err(|e| format!("Failed to save applied image: {}", e))?;
+    let temp_png_bytes = png_optimize::optimize(
+        temp_png_buf.into_inner(),
+        payload.png_optimization_level,
+        payload.strip_metadata,
+    )?;
+    fs::write(&temp_path, temp_png_bytes)
         .map_err(|e| format!("Failed to save applied image: {}", e))?;
 
     let (width, height) = img.dimensions();
@@ -384,6 +475,7 @@ fn apply_edits(app: tauri::AppHandle, payload: ApplyPayload) -> Result<ImageInfo
         width,
         height,
         data_url,
+        raw_source,
     })
 }
 
@@ -433,6 +525,7 @@ pub fn run() {
         .plugin(tauri_plugin_updater::Builder::new().build())
         .invoke_handler(tauri::generate_handler![
             open_image,
+            generate_blurhash,
             export_image,
             apply_edits,
             get_applied_path,
@@ -0,0 +1,28 @@
+//! Shared oxipng post-processing pass for PNG output.
+//!
+//! `export_image`'s PNG branch and `apply_edits`'s `_applied.png` temp file
+//! both want the same "losslessly shrink what we just encoded" step, so it
+//! lives here rather than being duplicated at each call site.
+
+/// Maps the UI's 0-6 slider to oxipng's optimization presets. `0` means the
+/// caller should skip optimization entirely rather than calling this.
+fn options_for_level(level: u8, strip_metadata: bool) -> oxipng::Options {
+    let mut options = oxipng::Options::from_preset(level.min(6));
+    if strip_metadata {
+        options.strip = oxipng::StripChunks::Safe;
+    }
+    options
+}
+
+/// Runs an in-memory oxipng pass over already-encoded PNG bytes, trying
+/// oxipng's filter/deflate search and keeping the smallest result. `level`
+/// of `0` is a no-op that returns `png_bytes` unchanged.
+pub fn optimize(png_bytes: Vec<u8>, level: u8, strip_metadata: bool) -> Result<Vec<u8>, String> {
+    if level == 0 {
+        return Ok(png_bytes);
+    }
+
+    let options = options_for_level(level, strip_metadata);
+    oxipng::optimize_from_memory(&png_bytes, &options)
+        .map_err(|e| format!("Failed to optimize PNG: {}", e))
+}
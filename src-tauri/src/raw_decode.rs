@@ -0,0 +1,208 @@
+//! Camera RAW front-end for `open_image`/`export_image`/`apply_edits`.
+//!
+//! `image::open` only understands conventional raster formats, so RAW files
+//! (ARW/CR2/NEF/DNG, ...) are routed here instead: `rawloader` pulls the raw
+//! sensor data and metadata out of the container, then this module
+//! demosaics, applies the camera white balance, and maps into sRGB so the
+//! result is a normal `DynamicImage` that flows through the rest of the
+//! pipeline unmodified.
+
+use image::{DynamicImage, RgbaImage};
+
+const RAW_EXTENSIONS: &[&str] = &["arw", "cr2", "cr3", "nef", "dng", "raf", "rw2", "orf"];
+
+/// Metadata surfaced alongside a decoded RAW image so the frontend can show
+/// "shot on" info and know the preview may still be a thumbnail stand-in.
+#[derive(Debug, Clone, Default, serde::Serialize, serde::Deserialize)]
+pub struct RawSourceInfo {
+    pub is_raw: bool,
+    pub camera_make: Option<String>,
+    pub camera_model: Option<String>,
+}
+
+pub fn is_raw_path(path: &str) -> bool {
+    std::path::Path::new(path)
+        .extension()
+        .and_then(|ext| ext.to_str())
+        .map(|ext| RAW_EXTENSIONS.contains(&ext.to_lowercase().as_str()))
+        .unwrap_or(false)
+}
+
+/// Extracts the embedded preview JPEG from a RAW file's EXIF IFD1, if one is
+/// present, along with the camera make/model read from the same EXIF
+/// container. Used so `open_image` can show something instantly, with the
+/// "shot on" info already attached, while the full sensor decode (which is
+/// much slower) runs.
+pub fn embedded_thumbnail(path: &str) -> Option<(DynamicImage, RawSourceInfo)> {
+    let file = std::fs::File::open(path).ok()?;
+    let mut reader = std::io::BufReader::new(file);
+    let exif = exif::Reader::new()
+        .read_from_container(&mut reader)
+        .ok()?;
+
+    let offset = exif
+        .get_field(exif::Tag::JPEGInterchangeFormat, exif::In::THUMBNAIL)?
+        .value
+        .get_uint(0)? as usize;
+    let length = exif
+        .get_field(exif::Tag::JPEGInterchangeFormatLength, exif::In::THUMBNAIL)?
+        .value
+        .get_uint(0)? as usize;
+
+    let bytes = std::fs::read(path).ok()?;
+    let jpeg_bytes = bytes.get(offset..offset + length)?;
+    let thumbnail = image::load_from_memory(jpeg_bytes).ok()?;
+
+    let exif_string = |tag: exif::Tag| {
+        exif.get_field(tag, exif::In::PRIMARY)
+            .map(|field| field.display_value().to_string())
+            .filter(|s| !s.is_empty())
+    };
+
+    let info = RawSourceInfo {
+        is_raw: true,
+        camera_make: exif_string(exif::Tag::Make),
+        camera_model: exif_string(exif::Tag::Model),
+    };
+
+    Some((thumbnail, info))
+}
+
+/// Decodes a RAW file into a full-resolution `DynamicImage`: demosaic,
+/// camera white balance, then a camera-RGB -> sRGB color matrix.
+pub fn decode(path: &str) -> Result<(DynamicImage, RawSourceInfo), String> {
+    let raw = rawloader::decode_file(path).map_err(|e| format!("Failed to decode RAW: {}", e))?;
+
+    let data = match &raw.data {
+        rawloader::RawImageData::Integer(data) => data,
+        rawloader::RawImageData::Float(_) => {
+            return Err("Floating point RAW sensor data is not supported".to_string());
+        }
+    };
+
+    let (width, height) = (raw.width, raw.height);
+    let wb = raw.wb_coeffs;
+
+    // Normalize + white-balance each sample in place into a float buffer,
+    // keeping each sample tagged with its CFA color for the demosaic pass.
+    // Black/white levels are per-channel (sensors can clip or floor each
+    // color differently), so they're indexed by the same CFA color as the
+    // sample rather than assumed uniform across the frame.
+    let mut channel = vec![[0.0f32; 4]; width * height]; // R, G, B, coverage count placeholder unused per-channel
+    for row in 0..height {
+        for col in 0..width {
+            let idx = row * width + col;
+            let color = raw.cfa.color_at(row, col);
+            let black = raw.blacklevels[color] as f32;
+            let white = raw.whitelevels[color] as f32;
+            let raw_value = data[idx] as f32;
+            let normalized = ((raw_value - black) / (white - black)).clamp(0.0, 1.0);
+            let balanced = normalized * wb[color].max(0.0);
+            channel[idx][color] = balanced;
+        }
+    }
+
+    // Bilinear demosaic: each output channel at each pixel averages the
+    // nearest same-colored samples in a small window, which is pattern-
+    // agnostic (works for RGGB/BGGR/... without hardcoding phase).
+    let mut rgb = vec![[0.0f32; 3]; width * height];
+    for row in 0..height {
+        for col in 0..width {
+            for out_channel in 0..3 {
+                let mut sum = 0.0f32;
+                let mut count = 0u32;
+                for dy in -1i32..=1 {
+                    for dx in -1i32..=1 {
+                        let sr = row as i32 + dy;
+                        let sc = col as i32 + dx;
+                        if sr < 0 || sc < 0 || sr >= height as i32 || sc >= width as i32 {
+                            continue;
+                        }
+                        let (sr, sc) = (sr as usize, sc as usize);
+                        if raw.cfa.color_at(sr, sc) == out_channel {
+                            sum += channel[sr * width + sc][out_channel];
+                            count += 1;
+                        }
+                    }
+                }
+                let idx = row * width + col;
+                rgb[idx][out_channel] = if count > 0 { sum / count as f32 } else { 0.0 };
+            }
+        }
+    }
+
+    // Camera RGB -> sRGB color matrix, falling back to identity if the
+    // camera profile isn't known to rawloader. An unrecognized camera
+    // leaves rawloader's internal xyz_to_cam all zero, and cam_to_xyz()
+    // pseudo-inverts that, so the "unknown" case shows up as NaN here, not
+    // as zeroes.
+    let cam_to_xyz = raw.cam_to_xyz();
+    let matrix = if cam_to_xyz.iter().flatten().any(|v| v.is_nan()) {
+        [[1.0, 0.0, 0.0], [0.0, 1.0, 0.0], [0.0, 0.0, 1.0]]
+    } else {
+        xyz_to_srgb_matrix(cam_to_xyz)
+    };
+
+    let mut out = RgbaImage::new(width as u32, height as u32);
+    for row in 0..height {
+        for col in 0..width {
+            let [r, g, b] = rgb[row * width + col];
+            let sr = matrix[0][0] * r + matrix[0][1] * g + matrix[0][2] * b;
+            let sg = matrix[1][0] * r + matrix[1][1] * g + matrix[1][2] * b;
+            let sb = matrix[2][0] * r + matrix[2][1] * g + matrix[2][2] * b;
+            out.put_pixel(
+                col as u32,
+                row as u32,
+                image::Rgba([
+                    to_srgb8(sr),
+                    to_srgb8(sg),
+                    to_srgb8(sb),
+                    255,
+                ]),
+            );
+        }
+    }
+
+    let info = RawSourceInfo {
+        is_raw: true,
+        camera_make: Some(raw.clean_make.clone()).filter(|s| !s.is_empty()),
+        camera_model: Some(raw.clean_model.clone()).filter(|s| !s.is_empty()),
+    };
+
+    Ok((DynamicImage::ImageRgba8(out), info))
+}
+
+fn to_srgb8(linear: f32) -> u8 {
+    let v = linear.clamp(0.0, 1.0);
+    let srgb = if v <= 0.0031308 {
+        v * 12.92
+    } else {
+        1.055 * v.powf(1.0 / 2.4) - 0.055
+    };
+    (srgb * 255.0).round().clamp(0.0, 255.0) as u8
+}
+
+/// Combines a camera-RGB -> XYZ matrix with the standard XYZ -> sRGB matrix
+/// (D65) into a single camera-RGB -> sRGB matrix. `rawloader` reports
+/// `cam_to_xyz` as 3 rows (XYZ) by 4 columns (camera channels, the 4th being
+/// a second green/emerald sample); we only demosaic to 3 channels, so the
+/// 4th column is dropped.
+fn xyz_to_srgb_matrix(cam_to_xyz: [[f32; 4]; 3]) -> [[f32; 3]; 3] {
+    const XYZ_TO_SRGB: [[f32; 3]; 3] = [
+        [3.240454, -1.537139, -0.498531],
+        [-0.969266, 1.876011, 0.041556],
+        [0.055643, -0.204026, 1.057225],
+    ];
+
+    let mut result = [[0.0f32; 3]; 3];
+    for i in 0..3 {
+        for j in 0..3 {
+            let mut sum = 0.0;
+            for k in 0..3 {
+                sum += XYZ_TO_SRGB[i][k] * cam_to_xyz[k][j];
+            }
+            result[i][j] = sum;
+        }
+    }
+    result
+}
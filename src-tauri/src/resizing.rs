@@ -0,0 +1,279 @@
+//! Fast, reusable image resampling shared by `export_image`, `scale_then_crop`,
+//! and `apply_edits`.
+//!
+//! Resampling is done in two separable passes (horizontal then vertical),
+//! each built from a set of per-destination-pixel convolution weights that
+//! are computed once per axis at the start of each `resize` call and reused
+//! for every row (or column) of that pass. The weights are not cached between
+//! calls. The inner
+//! convolution is runtime-dispatched to AVX2/SSE4.1 on x86_64 or NEON on
+//! aarch64 when available, falling back to scalar code elsewhere.
+
+use image::RgbaImage;
+
+/// Resampling kernels exposed to the frontend via `resize_filter` on
+/// `ExportPayload`/`ApplyPayload`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ResizeFilter {
+    Point,
+    Triangle,
+    CatmullRom,
+    Lanczos3,
+}
+
+impl ResizeFilter {
+    pub fn parse(name: &str) -> Self {
+        match name {
+            "point" | "nearest" => ResizeFilter::Point,
+            "triangle" | "bilinear" => ResizeFilter::Triangle,
+            "catmull_rom" | "catmull-rom" => ResizeFilter::CatmullRom,
+            _ => ResizeFilter::Lanczos3,
+        }
+    }
+
+    fn support(self) -> f64 {
+        match self {
+            ResizeFilter::Point => 0.5,
+            ResizeFilter::Triangle => 1.0,
+            ResizeFilter::CatmullRom => 2.0,
+            ResizeFilter::Lanczos3 => 3.0,
+        }
+    }
+
+    fn weight(self, x: f64) -> f64 {
+        match self {
+            ResizeFilter::Point => {
+                if x.abs() < 0.5 {
+                    1.0
+                } else {
+                    0.0
+                }
+            }
+            ResizeFilter::Triangle => (1.0 - x.abs()).max(0.0),
+            ResizeFilter::CatmullRom => {
+                let ax = x.abs();
+                if ax < 1.0 {
+                    1.5 * ax.powi(3) - 2.5 * ax.powi(2) + 1.0
+                } else if ax < 2.0 {
+                    -0.5 * ax.powi(3) + 2.5 * ax.powi(2) - 4.0 * ax + 2.0
+                } else {
+                    0.0
+                }
+            }
+            ResizeFilter::Lanczos3 => {
+                if x == 0.0 {
+                    1.0
+                } else if x.abs() < 3.0 {
+                    let px = std::f64::consts::PI * x;
+                    3.0 * (px.sin() * (px / 3.0).sin()) / (px * px)
+                } else {
+                    0.0
+                }
+            }
+        }
+    }
+}
+
+/// Per-destination-pixel contribution: the first source index it reads from,
+/// how many source samples it covers, and where those samples' weights
+/// start in `weights` (precomputed once so the resize loops don't re-derive
+/// it per pixel).
+struct AxisCoefficients {
+    bounds: Vec<(u32, u32)>,        // (first source index, sample count) per dst index
+    weight_offsets: Vec<usize>,     // prefix-sum offset into `weights` per dst index
+    weights: Vec<f32>,
+}
+
+fn build_coefficients(src_size: u32, dst_size: u32, filter: ResizeFilter) -> AxisCoefficients {
+    let scale = src_size as f64 / dst_size as f64;
+    let filter_scale = scale.max(1.0);
+    let support = filter.support() * filter_scale;
+
+    let mut bounds = Vec::with_capacity(dst_size as usize);
+    let mut weight_offsets = Vec::with_capacity(dst_size as usize);
+    let mut weights = Vec::new();
+
+    for dst_x in 0..dst_size {
+        let center = (dst_x as f64 + 0.5) * scale;
+        let left = ((center - support).floor() as i64).max(0);
+        let right = ((center + support).ceil() as i64).min(src_size as i64 - 1);
+
+        weight_offsets.push(weights.len());
+        let mut row_weights = Vec::new();
+        let mut sum = 0.0;
+        for src_x in left..=right {
+            let w = filter.weight((src_x as f64 + 0.5 - center) / filter_scale);
+            row_weights.push(w);
+            sum += w;
+        }
+        if sum != 0.0 {
+            for w in &mut row_weights {
+                *w /= sum;
+            }
+        }
+        weights.extend(row_weights.iter().map(|w| *w as f32));
+        bounds.push((left as u32, (right - left + 1) as u32));
+    }
+
+    AxisCoefficients {
+        bounds,
+        weight_offsets,
+        weights,
+    }
+}
+
+/// Weighted sum of `count` consecutive f32 pixel samples against `weights`,
+/// dispatched at runtime to the widest SIMD instruction set available, with
+/// a scalar fallback for everything else.
+fn weighted_sum(samples: &[f32], weights: &[f32]) -> f32 {
+    #[cfg(target_arch = "x86_64")]
+    {
+        if is_x86_feature_detected!("avx2") {
+            return unsafe { weighted_sum_avx2(samples, weights) };
+        }
+        if is_x86_feature_detected!("sse4.1") {
+            return unsafe { weighted_sum_sse41(samples, weights) };
+        }
+    }
+    #[cfg(target_arch = "aarch64")]
+    {
+        if std::arch::is_aarch64_feature_detected!("neon") {
+            return unsafe { weighted_sum_neon(samples, weights) };
+        }
+    }
+    weighted_sum_scalar(samples, weights)
+}
+
+fn weighted_sum_scalar(samples: &[f32], weights: &[f32]) -> f32 {
+    samples
+        .iter()
+        .zip(weights)
+        .fold(0.0f32, |acc, (s, w)| acc + s * w)
+}
+
+#[cfg(target_arch = "x86_64")]
+#[target_feature(enable = "avx2")]
+unsafe fn weighted_sum_avx2(samples: &[f32], weights: &[f32]) -> f32 {
+    use std::arch::x86_64::*;
+
+    let len = samples.len();
+    let mut acc = _mm256_setzero_ps();
+    let mut i = 0;
+    while i + 8 <= len {
+        let s = _mm256_loadu_ps(samples.as_ptr().add(i));
+        let w = _mm256_loadu_ps(weights.as_ptr().add(i));
+        acc = _mm256_add_ps(acc, _mm256_mul_ps(s, w));
+        i += 8;
+    }
+    let mut lanes = [0.0f32; 8];
+    _mm256_storeu_ps(lanes.as_mut_ptr(), acc);
+    let mut sum: f32 = lanes.iter().sum();
+    while i < len {
+        sum += samples[i] * weights[i];
+        i += 1;
+    }
+    sum
+}
+
+#[cfg(target_arch = "x86_64")]
+#[target_feature(enable = "sse4.1")]
+unsafe fn weighted_sum_sse41(samples: &[f32], weights: &[f32]) -> f32 {
+    use std::arch::x86_64::*;
+
+    let len = samples.len();
+    let mut acc = _mm_setzero_ps();
+    let mut i = 0;
+    while i + 4 <= len {
+        let s = _mm_loadu_ps(samples.as_ptr().add(i));
+        let w = _mm_loadu_ps(weights.as_ptr().add(i));
+        acc = _mm_add_ps(acc, _mm_mul_ps(s, w));
+        i += 4;
+    }
+    let mut lanes = [0.0f32; 4];
+    _mm_storeu_ps(lanes.as_mut_ptr(), acc);
+    let mut sum: f32 = lanes.iter().sum();
+    while i < len {
+        sum += samples[i] * weights[i];
+        i += 1;
+    }
+    sum
+}
+
+#[cfg(target_arch = "aarch64")]
+#[target_feature(enable = "neon")]
+unsafe fn weighted_sum_neon(samples: &[f32], weights: &[f32]) -> f32 {
+    use std::arch::aarch64::*;
+
+    let len = samples.len();
+    let mut acc = vdupq_n_f32(0.0);
+    let mut i = 0;
+    while i + 4 <= len {
+        let s = vld1q_f32(samples.as_ptr().add(i));
+        let w = vld1q_f32(weights.as_ptr().add(i));
+        acc = vmlaq_f32(acc, s, w);
+        i += 4;
+    }
+    let mut sum = vaddvq_f32(acc);
+    while i < len {
+        sum += samples[i] * weights[i];
+        i += 1;
+    }
+    sum
+}
+
+/// Resamples `src` to `dst_w`x`dst_h` using separable convolution. Returns a
+/// plain copy when the destination size matches the source size; at a 1:1
+/// scale each filter weighs the matching source pixel 1 and its neighbours
+/// (up to float rounding) 0, so convolving would only reproduce the source
+/// and resampling is skipped.
+pub fn resize(src: &RgbaImage, dst_w: u32, dst_h: u32, filter: ResizeFilter) -> RgbaImage {
+    let (src_w, src_h) = (src.width(), src.height());
+    if dst_w == src_w && dst_h == src_h {
+        return src.clone();
+    }
+
+    let h_coeffs = build_coefficients(src_w, dst_w, filter);
+    let v_coeffs = build_coefficients(src_h, dst_h, filter);
+
+    // Horizontal pass: src_w x src_h -> dst_w x src_h, kept in f32 to avoid
+    // compounding rounding error before the vertical pass.
+    let mut intermediate = vec![0.0f32; dst_w as usize * src_h as usize * 4];
+    let mut channel_buf: Vec<f32> = Vec::new();
+    for y in 0..src_h {
+        for (dst_x, &(start, count)) in h_coeffs.bounds.iter().enumerate() {
+            let weight_start = h_coeffs.weight_offsets[dst_x];
+            let weights = &h_coeffs.weights[weight_start..weight_start + count as usize];
+            for channel in 0..4 {
+                channel_buf.clear();
+                channel_buf.extend(
+                    (0..count).map(|k| src.get_pixel(start + k, y)[channel] as f32),
+                );
+                let value = weighted_sum(&channel_buf, weights);
+                let idx = (y as usize * dst_w as usize + dst_x) * 4 + channel;
+                intermediate[idx] = value;
+            }
+        }
+    }
+
+    // Vertical pass: dst_w x src_h -> dst_w x dst_h.
+    let mut out = RgbaImage::new(dst_w, dst_h);
+    for (dst_y, &(start, count)) in v_coeffs.bounds.iter().enumerate() {
+        let weight_start = v_coeffs.weight_offsets[dst_y];
+        let weights = &v_coeffs.weights[weight_start..weight_start + count as usize];
+        for x in 0..dst_w {
+            let mut pixel = [0u8; 4];
+            for (channel, out_channel) in pixel.iter_mut().enumerate() {
+                channel_buf.clear();
+                channel_buf.extend((0..count).map(|k| {
+                    let idx = ((start + k) as usize * dst_w as usize + x as usize) * 4 + channel;
+                    intermediate[idx]
+                }));
+                let value = weighted_sum(&channel_buf, weights);
+                *out_channel = value.round().clamp(0.0, 255.0) as u8;
+            }
+            out.put_pixel(x, dst_y as u32, image::Rgba(pixel));
+        }
+    }
+
+    out
+}